@@ -0,0 +1,49 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    cert_chain.rs
+
+Abstract:
+
+    File contains the metadata for the first page (`offset == 0`) of a
+    paged `GetCertificateChain` response: the total length of the full
+    certificate chain and its SHA-384 digest, so a caller paging through
+    the chain in bounded-size chunks can verify it reassembled the chain
+    correctly without re-fetching it in one shot.
+
+    `ChainDigest::compute` computes both values over a chain its caller
+    supplies. Nothing in this crate attaches them to a `GetCertificateChain`
+    response; that command's handler is not part of this crate.
+
+--*/
+
+use sha2::{Digest, Sha384};
+
+/// Size in bytes of the SHA-384 digest of the full certificate chain
+pub const CHAIN_DIGEST_SIZE: usize = 48;
+
+/// Total length and digest of the full certificate chain, for the
+/// `offset == 0` page of a paged `GetCertificateChain` response.
+pub struct ChainDigest {
+    /// Total length in bytes of the full certificate chain
+    pub total_length: u32,
+    /// SHA-384 digest of the full certificate chain
+    pub digest: [u8; CHAIN_DIGEST_SIZE],
+}
+
+impl ChainDigest {
+    /// Compute the total length and SHA-384 digest of `full_chain`.
+    pub fn compute(full_chain: &[u8]) -> Self {
+        let mut hasher = Sha384::new();
+        hasher.update(full_chain);
+        let mut digest = [0u8; CHAIN_DIGEST_SIZE];
+        digest.copy_from_slice(&hasher.finalize());
+        Self {
+            total_length: full_chain.len() as u32,
+            digest,
+        }
+    }
+}
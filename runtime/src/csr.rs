@@ -0,0 +1,223 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    csr.rs
+
+Abstract:
+
+    File contains DER encoding of a PKCS#10 CertificationRequest (CSR) for
+    the DPE CertifyKey command's `FORMAT_CSR` response, as an alternative to
+    the full X.509 leaf certificate produced by `FORMAT_X509`.
+
+    `build_certify_key_csr` takes the derived key's public coordinates and a
+    `sign` closure from its caller. Nothing in this crate calls it from a
+    CertifyKey command handler; that handler and its `FORMAT_CSR` dispatch
+    are not part of this crate.
+
+--*/
+
+/// Maximum size of the encoded CertificationRequest this module produces
+pub const MAX_CSR_SIZE: usize = 512;
+
+/// OID content bytes (DER value, without tag/length) for id-ecPublicKey
+const OID_EC_PUBLIC_KEY: [u8; 7] = [0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+
+/// OID content bytes for the secp384r1 named curve
+const OID_SECP384R1: [u8; 5] = [0x2B, 0x81, 0x04, 0x00, 0x22];
+
+/// OID content bytes for ecdsa-with-SHA384
+const OID_ECDSA_WITH_SHA384: [u8; 8] = [0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x03];
+
+/// OID content bytes for the commonName attribute type
+const OID_COMMON_NAME: [u8; 3] = [0x55, 0x04, 0x03];
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OID: u8 = 0x06;
+const TAG_UTF8_STRING: u8 = 0x0C;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_ATTRIBUTES: u8 = 0xA0;
+
+/// A minimal fixed-capacity DER writer. Caliptra's firmware targets run
+/// without an allocator, so CSR construction is bounded by `N` rather than
+/// growing a heap-allocated buffer.
+struct DerWriter<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> DerWriter<N> {
+    fn new() -> Self {
+        Self {
+            buf: [0u8; N],
+            len: 0,
+        }
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    fn write_raw(&mut self, data: &[u8]) {
+        self.buf[self.len..self.len + data.len()].copy_from_slice(data);
+        self.len += data.len();
+    }
+
+    fn write_len(&mut self, len: usize) {
+        if len < 0x80 {
+            self.write_raw(&[len as u8]);
+        } else {
+            let len_bytes = (len as u32).to_be_bytes();
+            let first_nonzero = len_bytes
+                .iter()
+                .position(|&b| b != 0)
+                .unwrap_or(len_bytes.len() - 1);
+            let trimmed = &len_bytes[first_nonzero..];
+            self.write_raw(&[0x80 | trimmed.len() as u8]);
+            self.write_raw(trimmed);
+        }
+    }
+
+    fn write_tlv(&mut self, tag: u8, content: &[u8]) {
+        self.write_raw(&[tag]);
+        self.write_len(content.len());
+        self.write_raw(content);
+    }
+
+    /// Write a DER INTEGER, padding with a leading zero byte if the most
+    /// significant bit of `value` is set (so it is not misread as negative).
+    fn write_unsigned_integer(&mut self, value: &[u8]) {
+        let mut start = 0;
+        while start < value.len() - 1 && value[start] == 0 {
+            start += 1;
+        }
+        let trimmed = &value[start..];
+
+        if trimmed[0] & 0x80 != 0 {
+            let mut padded = [0u8; 64];
+            padded[1..1 + trimmed.len()].copy_from_slice(trimmed);
+            self.write_tlv(TAG_INTEGER, &padded[..1 + trimmed.len()]);
+        } else {
+            self.write_tlv(TAG_INTEGER, trimmed);
+        }
+    }
+}
+
+/// Build a DER-encoded `CertificationRequestInfo` identifying `subject_cn`
+/// (a UTF8String, e.g. a hex-encoded context TCI, at most 96 bytes) and
+/// carrying the SECP384R1 `SubjectPublicKeyInfo` for `(pub_key_x, pub_key_y)`.
+fn encode_cert_request_info(subject_cn: &[u8], pub_key_x: &[u8; 48], pub_key_y: &[u8; 48]) -> DerWriter<384> {
+    // version INTEGER (v1 == 0)
+    let mut version = DerWriter::<8>::new();
+    version.write_tlv(TAG_INTEGER, &[0x00]);
+
+    // subject Name ::= RDNSequence ::= SEQUENCE OF RelativeDistinguishedName
+    let mut atv = DerWriter::<160>::new();
+    atv.write_tlv(TAG_OID, &OID_COMMON_NAME);
+    atv.write_tlv(TAG_UTF8_STRING, subject_cn);
+    let mut rdn = DerWriter::<176>::new();
+    rdn.write_tlv(TAG_SEQUENCE, atv.as_slice());
+    let mut name = DerWriter::<192>::new();
+    name.write_tlv(TAG_SET, rdn.as_slice());
+    let mut subject = DerWriter::<208>::new();
+    subject.write_tlv(TAG_SEQUENCE, name.as_slice());
+
+    // subjectPKInfo ::= SubjectPublicKeyInfo
+    let mut alg_id = DerWriter::<32>::new();
+    alg_id.write_tlv(TAG_OID, &OID_EC_PUBLIC_KEY);
+    alg_id.write_tlv(TAG_OID, &OID_SECP384R1);
+    let mut alg_id_seq = DerWriter::<40>::new();
+    alg_id_seq.write_tlv(TAG_SEQUENCE, alg_id.as_slice());
+
+    let mut point = [0u8; 97];
+    point[0] = 0x04; // uncompressed EC point
+    point[1..49].copy_from_slice(pub_key_x);
+    point[49..].copy_from_slice(pub_key_y);
+    let mut pub_key_bits = [0u8; 98];
+    pub_key_bits[0] = 0x00; // no unused bits
+    pub_key_bits[1..].copy_from_slice(&point);
+    let mut subject_pub_key = DerWriter::<104>::new();
+    subject_pub_key.write_tlv(TAG_BIT_STRING, &pub_key_bits);
+
+    let mut spki = DerWriter::<160>::new();
+    spki.write_raw(alg_id_seq.as_slice());
+    spki.write_raw(subject_pub_key.as_slice());
+    let mut spki_seq = DerWriter::<168>::new();
+    spki_seq.write_tlv(TAG_SEQUENCE, spki.as_slice());
+
+    // attributes [0] IMPLICIT Attributes ::= SET OF Attribute, empty here
+    let mut attributes = DerWriter::<8>::new();
+    attributes.write_tlv(TAG_ATTRIBUTES, &[]);
+
+    let mut cri_content = DerWriter::<320>::new();
+    cri_content.write_raw(version.as_slice());
+    cri_content.write_raw(subject.as_slice());
+    cri_content.write_raw(spki_seq.as_slice());
+    cri_content.write_raw(attributes.as_slice());
+
+    // The signature is computed over the DER encoding of the
+    // CertificationRequestInfo SEQUENCE, tag and length included.
+    let mut cri = DerWriter::<384>::new();
+    cri.write_tlv(TAG_SEQUENCE, cri_content.as_slice());
+    cri
+}
+
+/// Build a DER-encoded PKCS#10 `CertificationRequest` signed by the DPE
+/// derived key, writing it into `out` and returning the number of bytes
+/// written.
+///
+/// # Arguments
+///
+/// * `out` - Output buffer, must be at least [`MAX_CSR_SIZE`] bytes
+/// * `subject_cn` - UTF8String subject commonName, derived from the context TCI
+/// * `pub_key_x` - SECP384R1 derived public key X coordinate
+/// * `pub_key_y` - SECP384R1 derived public key Y coordinate
+/// * `sign` - Signs the DER-encoded `CertificationRequestInfo` (the DPE key
+///   hashes and signs it with ECDSA over SHA-384), returning `(r, s)`
+///
+/// # Returns
+///
+/// * `usize` - Number of bytes written to `out`
+pub fn build_certify_key_csr(
+    out: &mut [u8],
+    subject_cn: &[u8],
+    pub_key_x: &[u8; 48],
+    pub_key_y: &[u8; 48],
+    sign: impl FnOnce(&[u8]) -> ([u8; 48], [u8; 48]),
+) -> usize {
+    let cri = encode_cert_request_info(subject_cn, pub_key_x, pub_key_y);
+    let (sig_r, sig_s) = sign(cri.as_slice());
+
+    let mut signature = DerWriter::<112>::new();
+    signature.write_unsigned_integer(&sig_r);
+    signature.write_unsigned_integer(&sig_s);
+    let mut signature_seq = DerWriter::<120>::new();
+    signature_seq.write_tlv(TAG_SEQUENCE, signature.as_slice());
+
+    let mut sig_alg = DerWriter::<16>::new();
+    sig_alg.write_tlv(TAG_OID, &OID_ECDSA_WITH_SHA384);
+    let mut sig_alg_seq = DerWriter::<24>::new();
+    sig_alg_seq.write_tlv(TAG_SEQUENCE, sig_alg.as_slice());
+
+    let mut sig_bits = [0u8; 1 + 120];
+    sig_bits[0] = 0x00;
+    sig_bits[1..1 + signature_seq.as_slice().len()].copy_from_slice(signature_seq.as_slice());
+    let mut signature_bitstring = DerWriter::<130>::new();
+    signature_bitstring.write_tlv(TAG_BIT_STRING, &sig_bits[..1 + signature_seq.as_slice().len()]);
+
+    // certificationRequestInfo, signatureAlgorithm, signature
+    let mut content = DerWriter::<{ MAX_CSR_SIZE - 4 }>::new();
+    content.write_raw(cri.as_slice());
+    content.write_raw(sig_alg_seq.as_slice());
+    content.write_raw(signature_bitstring.as_slice());
+
+    let mut csr = DerWriter::<MAX_CSR_SIZE>::new();
+    csr.write_tlv(TAG_SEQUENCE, content.as_slice());
+
+    out[..csr.len].copy_from_slice(csr.as_slice());
+    csr.len
+}
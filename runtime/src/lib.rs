@@ -0,0 +1,19 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    lib.rs
+
+Abstract:
+
+    Crate root for the Caliptra runtime firmware library. This checkout
+    only carries the modules touched by this backlog; the rest of the
+    runtime crate (command dispatch, mailbox handling, DICE layering, etc.)
+    is not part of this change.
+
+--*/
+
+pub mod cert_chain;
+pub mod csr;
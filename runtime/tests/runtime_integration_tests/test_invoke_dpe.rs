@@ -18,6 +18,9 @@ use openssl::{
     ec::{EcGroup, EcKey},
     ecdsa::EcdsaSig,
     nid::Nid,
+    pkey::PKey,
+    sha::sha384,
+    x509::X509Req,
 };
 
 const TEST_LABEL: [u8; 48] = [
@@ -85,6 +88,67 @@ fn test_invoke_dpe_get_certificate_chain_cmd() {
     assert_ne!([0u8; 2048], cert_chain.certificate_chain);
 }
 
+#[test]
+fn test_invoke_dpe_get_certificate_chain_cmd_paged_digest() {
+    // The raw DPE `GetCertificateChainResp` only carries `certificate_size`
+    // and the page of `certificate_chain` bytes it was asked for; it has no
+    // total length or digest. The runtime's `cert_chain::ChainDigest`
+    // computes those two values over a full chain. This reconstructs the
+    // chain from paged reads, computes that digest over the reassembled
+    // bytes, and checks it against an independent single unpaged read.
+    let mut model = run_rt_test(None, None, None);
+
+    model.step_until(|m| {
+        m.soc_ifc().cptra_boot_status().read() == u32::from(RtBootStatus::RtReadyForCommands)
+    });
+
+    const PAGE_SIZE: u32 = 256;
+    const FULL_SIZE: u32 = 2048;
+
+    let mut paged_chain = Vec::new();
+    let mut offset = 0;
+    loop {
+        let get_cert_chain_cmd = GetCertificateChainCmd {
+            offset,
+            size: PAGE_SIZE,
+        };
+        let resp = execute_dpe_cmd(
+            &mut model,
+            &mut Command::GetCertificateChain(get_cert_chain_cmd),
+        );
+        let Response::GetCertificateChain(cert_chain) = resp else {
+            panic!("Wrong response type!");
+        };
+
+        paged_chain.extend_from_slice(
+            &cert_chain.certificate_chain[..cert_chain.certificate_size as usize],
+        );
+        offset += cert_chain.certificate_size;
+
+        if cert_chain.certificate_size < PAGE_SIZE {
+            break;
+        }
+    }
+
+    let get_cert_chain_cmd = GetCertificateChainCmd {
+        offset: 0,
+        size: FULL_SIZE,
+    };
+    let resp = execute_dpe_cmd(
+        &mut model,
+        &mut Command::GetCertificateChain(get_cert_chain_cmd),
+    );
+    let Response::GetCertificateChain(full_chain) = resp else {
+        panic!("Wrong response type!");
+    };
+    let full_chain_bytes = &full_chain.certificate_chain[..full_chain.certificate_size as usize];
+    assert_eq!(paged_chain, full_chain_bytes);
+
+    let chain_digest = caliptra_runtime::cert_chain::ChainDigest::compute(&paged_chain);
+    assert_eq!(chain_digest.total_length as usize, full_chain_bytes.len());
+    assert_eq!(chain_digest.digest, sha384(full_chain_bytes));
+}
+
 #[test]
 fn test_invoke_dpe_sign_and_certify_key_cmds() {
     let mut model = run_rt_test(None, None, None);
@@ -126,6 +190,63 @@ fn test_invoke_dpe_sign_and_certify_key_cmds() {
     assert!(sig.verify(&TEST_DIGEST, &ecc_pub_key).unwrap());
 }
 
+// This checkout does not carry the runtime's command dispatch table, so
+// `FORMAT_CSR` is not driven through the mailbox `CertifyKey` command here.
+// Instead this drives `caliptra_runtime::csr` directly against the same key
+// the `CertifyKey`/`Sign` commands expose: it signs the SHA-384 digest of
+// the builder's CRI bytes with the derived key via `Sign`, then hands the
+// resulting `(r, s)` back to `build_certify_key_csr`.
+#[test]
+fn test_invoke_dpe_certify_key_csr_cmd() {
+    let mut model = run_rt_test(None, None, None);
+
+    let certify_key_cmd = CertifyKeyCmd {
+        handle: ContextHandle::default(),
+        label: TEST_LABEL,
+        flags: CertifyKeyFlags::empty(),
+        format: CertifyKeyCmd::FORMAT_X509,
+    };
+    let resp = execute_dpe_cmd(&mut model, &mut Command::CertifyKey(certify_key_cmd));
+    let Response::CertifyKey(certify_key_resp) = resp else {
+        panic!("Wrong response type!");
+    };
+
+    let mut cert = [0u8; caliptra_runtime::csr::MAX_CSR_SIZE];
+    let cert_size = caliptra_runtime::csr::build_certify_key_csr(
+        &mut cert,
+        &TEST_LABEL,
+        &certify_key_resp.derived_pubkey_x,
+        &certify_key_resp.derived_pubkey_y,
+        |cri_der| {
+            let sign_cmd = SignCmd {
+                handle: ContextHandle::default(),
+                label: TEST_LABEL,
+                flags: SignFlags::empty(),
+                digest: sha384(cri_der),
+            };
+            let resp = execute_dpe_cmd(&mut model, &mut Command::Sign(sign_cmd));
+            let Response::Sign(sign_resp) = resp else {
+                panic!("Wrong response type!");
+            };
+            (sign_resp.sig_r_or_hmac, sign_resp.sig_s)
+        },
+    );
+
+    let csr = X509Req::from_der(&cert[..cert_size]).unwrap();
+
+    let ecc_pub_key = EcKey::from_public_key_affine_coordinates(
+        &EcGroup::from_curve_name(Nid::SECP384R1).unwrap(),
+        &BigNum::from_slice(&certify_key_resp.derived_pubkey_x).unwrap(),
+        &BigNum::from_slice(&certify_key_resp.derived_pubkey_y).unwrap(),
+    )
+    .unwrap();
+
+    let csr_pub_key = csr.public_key().unwrap();
+    assert!(csr_pub_key.public_eq(&PKey::from_ec_key(ecc_pub_key.clone()).unwrap()));
+
+    assert!(csr.verify(&csr_pub_key).unwrap());
+}
+
 #[test]
 fn test_invoke_dpe_symmetric_sign() {
     let mut model = run_rt_test(None, None, None);
@@ -149,4 +270,10 @@ fn test_invoke_dpe_symmetric_sign() {
     assert_ne!(sign_resp.sig_r_or_hmac, [0u8; 48]);
     // s must be all 0s for hmac sign
     assert_eq!(sign_resp.sig_s, [0u8; 48]);
-}
\ No newline at end of file
+}
+
+// The tests in this file cover the SECP384R1 DPE profile only. This
+// checkout has no SECP256R1 profile (no `dpe` crate, no mailbox or
+// key-derivation glue for it, and no workspace `Cargo.toml` defining a
+// `dpe_profile_p256` feature), so there is no SECP256R1 Sign/CertifyKey
+// test here.
\ No newline at end of file
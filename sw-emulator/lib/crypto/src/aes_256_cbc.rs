@@ -0,0 +1,54 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    aes_256_cbc.rs
+
+Abstract:
+
+    File contains software implementation of AES-256-CBC decryption.
+
+--*/
+
+use aes::cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit};
+use aes::Aes256;
+
+/// Size in bytes of a single AES block
+const BLOCK_SIZE: usize = 16;
+
+/// AES-256-CBC decryption
+pub struct Aes256Cbc;
+
+impl Aes256Cbc {
+    /// Decrypt `ciphertext` into `plaintext` using AES-256 in CBC mode
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - 256-bit AES key
+    /// * `iv` - 128-bit initialization vector
+    /// * `ciphertext` - Input ciphertext, a multiple of the AES block size
+    /// * `plaintext` - Output plaintext buffer, same length as `ciphertext`
+    pub fn decrypt(key: &[u8; 32], iv: &[u8], ciphertext: &[u8], plaintext: &mut [u8]) {
+        assert_eq!(ciphertext.len(), plaintext.len());
+        assert_eq!(ciphertext.len() % BLOCK_SIZE, 0);
+
+        let cipher = Aes256::new(GenericArray::from_slice(key));
+        let mut prev_block = GenericArray::clone_from_slice(&iv[..BLOCK_SIZE]);
+
+        for (ct_chunk, pt_chunk) in ciphertext
+            .chunks(BLOCK_SIZE)
+            .zip(plaintext.chunks_mut(BLOCK_SIZE))
+        {
+            let ct_block = GenericArray::clone_from_slice(ct_chunk);
+            let mut block = ct_block;
+            cipher.decrypt_block(&mut block);
+            for i in 0..BLOCK_SIZE {
+                block[i] ^= prev_block[i];
+            }
+            pt_chunk.copy_from_slice(&block);
+            prev_block = ct_block;
+        }
+    }
+}
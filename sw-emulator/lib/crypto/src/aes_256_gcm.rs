@@ -0,0 +1,161 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    aes_256_gcm.rs
+
+Abstract:
+
+    File contains software implementation of authenticated AES-256-GCM
+    decryption (NIST SP 800-38D), used by the Deobfuscation Engine to verify
+    the integrity of the obfuscated UDS/FE before releasing them to the key
+    vault.
+
+--*/
+
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+use aes::Aes256;
+
+/// Size in bytes of a single AES/GHASH block
+const BLOCK_SIZE: usize = 16;
+
+/// Size in bytes of the GCM authentication tag
+const TAG_SIZE: usize = 16;
+
+/// AES-256-GCM authenticated decryption
+pub struct Aes256Gcm;
+
+impl Aes256Gcm {
+    /// Decrypt `ciphertext` into `plaintext` and verify the authentication
+    /// `tag` computed over `aad || ciphertext`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - 256-bit AES key
+    /// * `nonce` - 96-bit (12-byte) initialization vector
+    /// * `aad` - Additional authenticated data
+    /// * `ciphertext` - Input ciphertext
+    /// * `plaintext` - Output plaintext buffer, same length as `ciphertext`
+    /// * `tag` - 128-bit authentication tag to verify against
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - true if the tag verified. `plaintext` is written
+    ///   regardless; callers must discard it when this returns false.
+    pub fn decrypt(
+        key: &[u8; 32],
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+        plaintext: &mut [u8],
+        tag: &[u8; TAG_SIZE],
+    ) -> bool {
+        assert_eq!(ciphertext.len(), plaintext.len());
+        assert_eq!(nonce.len(), 12);
+
+        let cipher = Aes256::new(GenericArray::from_slice(key));
+
+        // H = AES_K(0^128)
+        let mut h = [0u8; BLOCK_SIZE];
+        let mut h_block = GenericArray::clone_from_slice(&h);
+        cipher.encrypt_block(&mut h_block);
+        h.copy_from_slice(&h_block);
+
+        // J0 = nonce || 0x00000001 (96-bit nonce case)
+        let mut j0 = [0u8; BLOCK_SIZE];
+        j0[..12].copy_from_slice(nonce);
+        j0[15] = 1;
+
+        // CTR-mode keystream: keystream_i = AES_K(inc32(J0)) for i >= 1
+        let mut counter = j0;
+        for (ct_chunk, pt_chunk) in ciphertext
+            .chunks(BLOCK_SIZE)
+            .zip(plaintext.chunks_mut(BLOCK_SIZE))
+        {
+            inc32(&mut counter);
+            let mut keystream = GenericArray::clone_from_slice(&counter);
+            cipher.encrypt_block(&mut keystream);
+            for (p, (c, k)) in pt_chunk.iter_mut().zip(ct_chunk.iter().zip(keystream.iter())) {
+                *p = c ^ k;
+            }
+        }
+
+        // S = GHASH_H(AAD || C || len(AAD) || len(C))
+        let mut s = [0u8; BLOCK_SIZE];
+        ghash_update(&mut s, &h, aad);
+        ghash_update(&mut s, &h, ciphertext);
+
+        let mut len_block = [0u8; BLOCK_SIZE];
+        len_block[..8].copy_from_slice(&((aad.len() as u64) * 8).to_be_bytes());
+        len_block[8..].copy_from_slice(&((ciphertext.len() as u64) * 8).to_be_bytes());
+        xor_mul(&mut s, &len_block, &h);
+
+        // T = S XOR AES_K(J0)
+        let mut ek_j0 = GenericArray::clone_from_slice(&j0);
+        cipher.encrypt_block(&mut ek_j0);
+        let mut computed_tag = [0u8; TAG_SIZE];
+        for i in 0..TAG_SIZE {
+            computed_tag[i] = s[i] ^ ek_j0[i];
+        }
+
+        constant_time_eq(&computed_tag, tag)
+    }
+}
+
+/// Increment the rightmost 32 bits of a 128-bit block (GCM's `inc32`)
+fn inc32(block: &mut [u8; BLOCK_SIZE]) {
+    let ctr = u32::from_be_bytes([block[12], block[13], block[14], block[15]]).wrapping_add(1);
+    block[12..].copy_from_slice(&ctr.to_be_bytes());
+}
+
+/// Fold `data` into the running GHASH state `s`, zero-padding the final
+/// partial block as specified in SP 800-38D.
+fn ghash_update(s: &mut [u8; BLOCK_SIZE], h: &[u8; BLOCK_SIZE], data: &[u8]) {
+    for chunk in data.chunks(BLOCK_SIZE) {
+        let mut block = [0u8; BLOCK_SIZE];
+        block[..chunk.len()].copy_from_slice(chunk);
+        xor_mul(s, &block, h);
+    }
+}
+
+/// `s = (s XOR block) . h` in GF(2^128), reduced modulo x^128+x^7+x^2+x+1,
+/// processing `h` bit-by-bit MSB-first with conditional XOR/reduction.
+fn xor_mul(s: &mut [u8; BLOCK_SIZE], block: &[u8; BLOCK_SIZE], h: &[u8; BLOCK_SIZE]) {
+    let mut v = [0u8; BLOCK_SIZE];
+    for i in 0..BLOCK_SIZE {
+        v[i] = s[i] ^ block[i];
+    }
+
+    let mut z = [0u8; BLOCK_SIZE];
+    for i in 0..128 {
+        let byte = i / 8;
+        let bit = 7 - (i % 8);
+        if (h[byte] >> bit) & 1 == 1 {
+            for k in 0..BLOCK_SIZE {
+                z[k] ^= v[k];
+            }
+        }
+
+        let lsb = v[15] & 1;
+        for k in (1..BLOCK_SIZE).rev() {
+            v[k] = (v[k] >> 1) | ((v[k - 1] & 1) << 7);
+        }
+        v[0] >>= 1;
+        if lsb == 1 {
+            v[0] ^= 0xe1;
+        }
+    }
+
+    *s = z;
+}
+
+/// Constant-time comparison of two authentication tags
+fn constant_time_eq(a: &[u8; TAG_SIZE], b: &[u8; TAG_SIZE]) -> bool {
+    let mut diff = 0u8;
+    for i in 0..TAG_SIZE {
+        diff |= a[i] ^ b[i];
+    }
+    diff == 0
+}
@@ -0,0 +1,20 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    lib.rs
+
+Abstract:
+
+    File contains software implementations of the cryptographic primitives
+    used by the Caliptra emulator peripherals.
+
+--*/
+
+mod aes_256_cbc;
+mod aes_256_gcm;
+
+pub use aes_256_cbc::Aes256Cbc;
+pub use aes_256_gcm::Aes256Gcm;
@@ -14,7 +14,7 @@ Abstract:
 
 use crate::{KeyVault, SocRegisters};
 use caliptra_emu_bus::{BusError, Clock, ReadWriteMemory, ReadWriteRegister, Timer, TimerAction};
-use caliptra_emu_crypto::Aes256Cbc;
+use caliptra_emu_crypto::{Aes256Cbc, Aes256Gcm};
 use caliptra_emu_derive::Bus;
 use caliptra_emu_types::{RvData, RvSize};
 use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
@@ -23,6 +23,9 @@ use tock_registers::register_bitfields;
 /// Initialization vector size
 const DOE_IV_SIZE: usize = 16;
 
+/// Size in bytes of the nonce consumed from the IV region for GCM flows
+const DOE_GCM_NONCE_SIZE: usize = 12;
+
 /// The number of CPU clock cycles it takes to perform the hash update action.
 const DOE_OP_TICKS: u64 = 1000;
 
@@ -39,6 +42,14 @@ register_bitfields! [
         ],
         DEST OFFSET(2) NUMBITS(3) [],
         FLOW_DONE OFFSET(5) NUMBITS(1) [],
+        // Bit positions below are newly-defined, previously-reserved bits;
+        // CMD/DEST/FLOW_DONE keep their original offsets so existing
+        // firmware/ROM writers of this register are unaffected.
+        /// Selects the authenticated AES-256-GCM deobfuscation path for
+        /// DEOBFUSCATE_UDS/DEOBFUSCATE_FE instead of the legacy AES-256-CBC one
+        GCM_MODE OFFSET(6) NUMBITS(1) [],
+        /// Set by `poll()` when a GCM deobfuscation flow fails tag verification
+        FLOW_ERROR OFFSET(7) NUMBITS(1) [],
     ],
 ];
 
@@ -120,13 +131,32 @@ impl Doe {
     fn poll(&mut self) {
         if self.timer.fired(&mut self.op_complete_action) {
             let key_id = self.control.reg.read(Control::DEST);
-            match self.control.reg.read_as_enum(Control::CMD) {
-                Some(Control::CMD::Value::DEOBFUSCATE_UDS) => self.unscramble_uds(key_id),
-                Some(Control::CMD::Value::DEOBFUSCATE_FE) => self.unscramble_fe(key_id),
-                Some(Control::CMD::Value::CLEAR_SECRETS) => self.clear_secrets(),
-                _ => {}
-            }
+            let gcm_mode = self.control.reg.is_set(Control::GCM_MODE);
+            let flow_error = match self.control.reg.read_as_enum(Control::CMD) {
+                Some(Control::CMD::Value::DEOBFUSCATE_UDS) if gcm_mode => {
+                    self.unscramble_uds_gcm(key_id)
+                }
+                Some(Control::CMD::Value::DEOBFUSCATE_UDS) => {
+                    self.unscramble_uds(key_id);
+                    false
+                }
+                Some(Control::CMD::Value::DEOBFUSCATE_FE) if gcm_mode => {
+                    self.unscramble_fe_gcm(key_id)
+                }
+                Some(Control::CMD::Value::DEOBFUSCATE_FE) => {
+                    self.unscramble_fe(key_id);
+                    false
+                }
+                Some(Control::CMD::Value::CLEAR_SECRETS) => {
+                    self.clear_secrets();
+                    false
+                }
+                _ => false,
+            };
             self.control.reg.write(Control::FLOW_DONE::SET);
+            if flow_error {
+                self.control.reg.modify(Control::FLOW_ERROR::SET);
+            }
         }
     }
 
@@ -164,6 +194,68 @@ impl Doe {
         self.key_vault.write_key(key_id, &plain_fe);
     }
 
+    /// Unscramble unique device secret (UDS) via authenticated AES-256-GCM
+    /// decryption and store it in the key vault
+    ///
+    /// # Argument
+    ///
+    /// * `key_id` - Key index to store the UDS
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - true if the authentication tag did not verify
+    fn unscramble_uds_gcm(&mut self, key_id: u32) -> bool {
+        let cipher_uds = self.soc_reg.uds_gcm();
+        let mut plain_uds = [0u8; 64];
+        let tag_ok = Aes256Gcm::decrypt(
+            &self.soc_reg.doe_key(),
+            &self.iv.data()[..DOE_GCM_NONCE_SIZE],
+            &[],
+            &cipher_uds,
+            &mut plain_uds[..cipher_uds.len()],
+            &self.soc_reg.uds_tag(),
+        );
+
+        if tag_ok {
+            self.key_vault.write_key(key_id, &plain_uds)
+        } else {
+            self.key_vault.write_key(key_id, &[0u8; 64])
+        }
+
+        !tag_ok
+    }
+
+    /// Unscramble field entropy via authenticated AES-256-GCM decryption and
+    /// store it in the key vault
+    ///
+    /// # Argument
+    ///
+    /// * `key_id` - Key index to store the field entropy
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - true if the authentication tag did not verify
+    fn unscramble_fe_gcm(&mut self, key_id: u32) -> bool {
+        let cipher_fe = self.soc_reg.field_entropy_gcm();
+        let mut plain_fe = [0u8; 64];
+        let tag_ok = Aes256Gcm::decrypt(
+            &self.soc_reg.doe_key(),
+            &self.iv.data()[..DOE_GCM_NONCE_SIZE],
+            &[],
+            &cipher_fe,
+            &mut plain_fe,
+            &self.soc_reg.fe_tag(),
+        );
+
+        if tag_ok {
+            self.key_vault.write_key(key_id, &plain_fe)
+        } else {
+            self.key_vault.write_key(key_id, &[0u8; 64])
+        }
+
+        !tag_ok
+    }
+
     /// Clear secrets
     fn clear_secrets(&mut self) {
         self.soc_reg.clear_secrets()
@@ -240,6 +332,179 @@ mod tests {
         assert_eq!(key_vault.read_key(2)[..48], PLAIN_TEXT_UDS);
     }
 
+    #[test]
+    fn test_deobfuscate_uds_gcm() {
+        // Only the first 12 bytes are consumed as the GCM nonce; the last 4
+        // are unused padding in the 16-byte IV region.
+        const NONCE: [u8; 16] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+
+        const PLAIN_TEXT_UDS: [u8; 48] = [
+            0x6B, 0xC1, 0xBE, 0xE2, 0x2E, 0x40, 0x9F, 0x96, 0xE9, 0x3D, 0x7E, 0x11, 0x73, 0x93,
+            0x17, 0x2A, 0xAE, 0x2D, 0x8A, 0x57, 0x1E, 0x3, 0xAC, 0x9C, 0x9E, 0xB7, 0x6F, 0xAC,
+            0x45, 0xAF, 0x8E, 0x51, 0x30, 0xC8, 0x1C, 0x46, 0xA3, 0x5C, 0xE4, 0x11, 0xE5, 0xFB,
+            0xC1, 0x19, 0x1A, 0xA, 0x52, 0xEF,
+        ];
+
+        let clock = Clock::new();
+        let key_vault = KeyVault::new();
+        let soc_reg = SocRegisters::new();
+        let mut doe = Doe::new(&clock, key_vault.clone(), soc_reg.clone());
+
+        for i in (0..NONCE.len()).step_by(4) {
+            assert_eq!(
+                doe.write(RvSize::Word, OFFSET_IV + i as RvAddr, make_word(i, &NONCE))
+                    .ok(),
+                Some(())
+            );
+        }
+
+        assert_eq!(
+            doe.write(
+                RvSize::Word,
+                OFFSET_CONTROL,
+                (Control::CMD::DEOBFUSCATE_UDS + Control::GCM_MODE::SET + Control::DEST.val(2))
+                    .value
+            )
+            .ok(),
+            Some(())
+        );
+
+        loop {
+            let status = InMemoryRegister::<u32, Control::Register>::new(
+                doe.read(RvSize::Word, OFFSET_CONTROL).unwrap(),
+            );
+
+            if status.is_set(Control::FLOW_DONE) {
+                break;
+            }
+
+            clock.increment_and_poll(1, &mut doe);
+        }
+
+        let status = InMemoryRegister::<u32, Control::Register>::new(
+            doe.read(RvSize::Word, OFFSET_CONTROL).unwrap(),
+        );
+        assert!(!status.is_set(Control::FLOW_ERROR));
+        assert_eq!(key_vault.read_key(2)[..48], PLAIN_TEXT_UDS);
+    }
+
+    #[test]
+    fn test_deobfuscate_uds_gcm_wrong_nonce() {
+        // A nonce that does not match the one the UDS ciphertext/tag pair was
+        // sealed under; tag verification must fail and no key material
+        // should reach the key vault.
+        const TAMPERED_NONCE: [u8; 16] = [0xff; 16];
+
+        let clock = Clock::new();
+        let key_vault = KeyVault::new();
+        let soc_reg = SocRegisters::new();
+        let mut doe = Doe::new(&clock, key_vault.clone(), soc_reg.clone());
+
+        for i in (0..TAMPERED_NONCE.len()).step_by(4) {
+            assert_eq!(
+                doe.write(
+                    RvSize::Word,
+                    OFFSET_IV + i as RvAddr,
+                    make_word(i, &TAMPERED_NONCE)
+                )
+                .ok(),
+                Some(())
+            );
+        }
+
+        assert_eq!(
+            doe.write(
+                RvSize::Word,
+                OFFSET_CONTROL,
+                (Control::CMD::DEOBFUSCATE_UDS + Control::GCM_MODE::SET + Control::DEST.val(2))
+                    .value
+            )
+            .ok(),
+            Some(())
+        );
+
+        loop {
+            let status = InMemoryRegister::<u32, Control::Register>::new(
+                doe.read(RvSize::Word, OFFSET_CONTROL).unwrap(),
+            );
+
+            if status.is_set(Control::FLOW_DONE) {
+                break;
+            }
+
+            clock.increment_and_poll(1, &mut doe);
+        }
+
+        let status = InMemoryRegister::<u32, Control::Register>::new(
+            doe.read(RvSize::Word, OFFSET_CONTROL).unwrap(),
+        );
+        assert!(status.is_set(Control::FLOW_ERROR));
+        assert_eq!(key_vault.read_key(2), [0u8; 64]);
+    }
+
+    #[test]
+    fn test_deobfuscate_fe_gcm() {
+        // Only the first 12 bytes are consumed as the GCM nonce; the last 4
+        // are unused padding in the 16-byte IV region.
+        const NONCE: [u8; 16] = [
+            0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+
+        const PLAIN_TEXT_FE: [u8; 64] = [
+            0xC6, 0x10, 0x65, 0x4D, 0xB4, 0xED, 0xA8, 0x53, 0xCF, 0x54, 0x6D, 0xEF, 0x52, 0x4E,
+            0xC1, 0x5F, 0x39, 0xEF, 0x9A, 0xB2, 0x4B, 0x12, 0x57, 0xAC, 0x30, 0xAB, 0x92, 0x10,
+            0xAD, 0xB1, 0x3E, 0xA0, 0x39, 0xEF, 0x9A, 0xB2, 0x4B, 0x12, 0x57, 0xAC, 0x30, 0xAB,
+            0x92, 0x10, 0xAD, 0xB1, 0x3E, 0xA0, 0x39, 0xEF, 0x9A, 0xB2, 0x4B, 0x12, 0x57, 0xAC,
+            0x30, 0xAB, 0x92, 0x10, 0xAD, 0xB1, 0x3E, 0xA0,
+        ];
+
+        let clock = Clock::new();
+        let key_vault = KeyVault::new();
+        let soc_reg = SocRegisters::new();
+        let mut doe = Doe::new(&clock, key_vault.clone(), soc_reg.clone());
+
+        for i in (0..NONCE.len()).step_by(4) {
+            assert_eq!(
+                doe.write(RvSize::Word, OFFSET_IV + i as RvAddr, make_word(i, &NONCE))
+                    .ok(),
+                Some(())
+            );
+        }
+
+        assert_eq!(
+            doe.write(
+                RvSize::Word,
+                OFFSET_CONTROL,
+                (Control::CMD::DEOBFUSCATE_FE + Control::GCM_MODE::SET + Control::DEST.val(3))
+                    .value
+            )
+            .ok(),
+            Some(())
+        );
+
+        loop {
+            let status = InMemoryRegister::<u32, Control::Register>::new(
+                doe.read(RvSize::Word, OFFSET_CONTROL).unwrap(),
+            );
+
+            if status.is_set(Control::FLOW_DONE) {
+                break;
+            }
+
+            clock.increment_and_poll(1, &mut doe);
+        }
+
+        let status = InMemoryRegister::<u32, Control::Register>::new(
+            doe.read(RvSize::Word, OFFSET_CONTROL).unwrap(),
+        );
+        assert!(!status.is_set(Control::FLOW_ERROR));
+        assert_eq!(key_vault.read_key(3), PLAIN_TEXT_FE);
+    }
+
     #[test]
     fn test_deobfuscate_fe() {
         const PLAIN_TEXT_FE: [u8; 64] = [
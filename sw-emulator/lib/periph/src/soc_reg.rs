@@ -0,0 +1,175 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    soc_reg.rs
+
+Abstract:
+
+    File contains the SoC-owned secret registers consumed by the
+    Deobfuscation Engine: the obfuscated UDS/field-entropy ciphertexts (one
+    pair sealed per supported deobfuscation mode) and the AES-256 key used to
+    unseal them.
+
+--*/
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Size in bytes of the unique device secret
+const UDS_SIZE: usize = 48;
+
+/// Size in bytes of the field entropy storage (only the first 64 bytes are
+/// consumed by the legacy CBC unscramble path)
+const FIELD_ENTROPY_SIZE: usize = 128;
+
+/// Size in bytes of the field entropy actually unscrambled
+const FIELD_ENTROPY_USED_SIZE: usize = 64;
+
+/// Size in bytes of the DOE AES-256 key
+const DOE_KEY_SIZE: usize = 32;
+
+/// Size in bytes of a GCM authentication tag
+const GCM_TAG_SIZE: usize = 16;
+
+struct SocRegistersImpl {
+    /// AES-256 key used to unscramble the UDS and field entropy
+    doe_key: [u8; DOE_KEY_SIZE],
+
+    /// UDS ciphertext sealed with AES-256-CBC
+    uds: [u8; UDS_SIZE],
+
+    /// UDS ciphertext sealed with AES-256-GCM
+    uds_gcm: [u8; UDS_SIZE],
+
+    /// AES-256-GCM authentication tag for `uds_gcm`
+    uds_tag: [u8; GCM_TAG_SIZE],
+
+    /// Field entropy ciphertext sealed with AES-256-CBC
+    field_entropy: [u8; FIELD_ENTROPY_SIZE],
+
+    /// Field entropy ciphertext sealed with AES-256-GCM
+    field_entropy_gcm: [u8; FIELD_ENTROPY_USED_SIZE],
+
+    /// AES-256-GCM authentication tag for `field_entropy_gcm`
+    fe_tag: [u8; GCM_TAG_SIZE],
+}
+
+/// SoC Registers
+#[derive(Clone)]
+pub struct SocRegisters {
+    regs: Rc<RefCell<SocRegistersImpl>>,
+}
+
+impl SocRegisters {
+    /// Create a new instance of the SoC registers, seeded with fixed
+    /// known-answer secrets so that emulator tests are reproducible.
+    pub fn new() -> Self {
+        Self {
+            regs: Rc::new(RefCell::new(SocRegistersImpl {
+                doe_key: [
+                    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C,
+                    0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19,
+                    0x1A, 0x1B, 0x1C, 0x1D, 0x1E, 0x1F,
+                ],
+                uds: [
+                    0xE0, 0x78, 0x36, 0x27, 0x7C, 0x86, 0x2D, 0x6E, 0x5B, 0xE3, 0x7B, 0x99, 0x0B,
+                    0xD2, 0xD6, 0x41, 0xA1, 0xEC, 0x51, 0x99, 0x33, 0xE6, 0xA9, 0x3A, 0xE5, 0xCF,
+                    0xD0, 0x1D, 0x9D, 0x4F, 0x31, 0x48, 0x71, 0xA6, 0xD0, 0x8E, 0x56, 0xDD, 0x29,
+                    0x90, 0x9F, 0xF9, 0x67, 0x16, 0xDA, 0xF0, 0x6B, 0x06,
+                ],
+                uds_gcm: [
+                    0x2C, 0xC3, 0x68, 0xF9, 0xEB, 0xA5, 0x5D, 0x8D, 0x64, 0x7C, 0xE9, 0x9A, 0xC2,
+                    0x7A, 0x6F, 0x47, 0x2D, 0xFB, 0x0D, 0x63, 0xEE, 0x78, 0xF3, 0xE0, 0xA6, 0xD0,
+                    0x8A, 0x29, 0x58, 0xC6, 0x8E, 0xE3, 0x31, 0xD8, 0xB2, 0xBA, 0x0C, 0x9D, 0xF6,
+                    0x89, 0x91, 0x5F, 0xBE, 0xF4, 0x92, 0x8D, 0x7A, 0xD7,
+                ],
+                uds_tag: [
+                    0xB0, 0x3C, 0xAA, 0x98, 0xB1, 0x5F, 0x01, 0xDD, 0xC2, 0x02, 0xA5, 0x01, 0xDF,
+                    0xA1, 0xA3, 0x0D,
+                ],
+                field_entropy: [
+                    0xE0, 0xF6, 0x3D, 0x14, 0xAE, 0xDE, 0x0E, 0xF5, 0xF2, 0x98, 0x3E, 0xA5, 0xDF,
+                    0x7C, 0x6D, 0x71, 0xB8, 0x20, 0xAF, 0xF0, 0x6D, 0xCE, 0x38, 0x41, 0x8E, 0x3A,
+                    0x80, 0xCC, 0x67, 0xDD, 0xF9, 0x59, 0x6A, 0x48, 0x52, 0xC0, 0x6E, 0x5A, 0xA6,
+                    0x29, 0x75, 0x02, 0xAE, 0xCD, 0x5F, 0x3C, 0xEA, 0xDA, 0xFD, 0x56, 0x6D, 0xEF,
+                    0x06, 0xC9, 0x2D, 0x12, 0x7A, 0xD9, 0x79, 0xC7, 0x30, 0xEE, 0xCB, 0x89, 0x40,
+                    0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4A, 0x4B, 0x4C, 0x4D,
+                    0x4E, 0x4F, 0x50, 0x51, 0x52, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A,
+                    0x5B, 0x5C, 0x5D, 0x5E, 0x5F, 0x60, 0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67,
+                    0x68, 0x69, 0x6A, 0x6B, 0x6C, 0x6D, 0x6E, 0x6F, 0x70, 0x71, 0x72, 0x73, 0x74,
+                    0x75, 0x76, 0x77, 0x78, 0x79, 0x7A, 0x7B, 0x7C, 0x7D, 0x7E, 0x7F,
+                ],
+                field_entropy_gcm: [
+                    0x5E, 0xEE, 0x0C, 0x95, 0xCB, 0x9B, 0x54, 0x00, 0xF8, 0x7E, 0x25, 0x3A, 0xC3,
+                    0xC5, 0xE7, 0xB8, 0x6C, 0xEC, 0x77, 0x5C, 0xBD, 0x6F, 0x6C, 0x06, 0xAB, 0x09,
+                    0x68, 0xF0, 0x49, 0x11, 0xFE, 0x5A, 0x15, 0x18, 0xF0, 0x97, 0xAA, 0xD7, 0x71,
+                    0xCD, 0x2F, 0x58, 0x9C, 0x42, 0x43, 0x1D, 0x24, 0x7A, 0xB5, 0xB9, 0x21, 0x63,
+                    0xE2, 0xE6, 0x43, 0x1F, 0x7E, 0x54, 0xE8, 0x4C, 0x17, 0x5C, 0x74, 0x18,
+                ],
+                fe_tag: [
+                    0xDB, 0x78, 0xF6, 0xDA, 0x3E, 0x02, 0xC9, 0x7F, 0xB3, 0xD9, 0x08, 0xF2, 0xA8,
+                    0x0D, 0x00, 0x7B,
+                ],
+            })),
+        }
+    }
+
+    /// AES-256 key used to unscramble the UDS and field entropy
+    pub fn doe_key(&self) -> [u8; DOE_KEY_SIZE] {
+        self.regs.borrow().doe_key
+    }
+
+    /// Obfuscated UDS, sealed with AES-256-CBC
+    pub fn uds(&self) -> [u8; UDS_SIZE] {
+        self.regs.borrow().uds
+    }
+
+    /// Obfuscated UDS, sealed with AES-256-GCM. Pair with [`Self::uds_tag`]
+    /// and a 96-bit nonce to authenticate before release to the key vault.
+    pub fn uds_gcm(&self) -> [u8; UDS_SIZE] {
+        self.regs.borrow().uds_gcm
+    }
+
+    /// AES-256-GCM authentication tag for [`Self::uds_gcm`]
+    pub fn uds_tag(&self) -> [u8; GCM_TAG_SIZE] {
+        self.regs.borrow().uds_tag
+    }
+
+    /// Obfuscated field entropy, sealed with AES-256-CBC
+    pub fn field_entropy(&self) -> [u8; FIELD_ENTROPY_SIZE] {
+        self.regs.borrow().field_entropy
+    }
+
+    /// Obfuscated field entropy, sealed with AES-256-GCM. Pair with
+    /// [`Self::fe_tag`] and a 96-bit nonce to authenticate before release to
+    /// the key vault.
+    pub fn field_entropy_gcm(&self) -> [u8; FIELD_ENTROPY_USED_SIZE] {
+        self.regs.borrow().field_entropy_gcm
+    }
+
+    /// AES-256-GCM authentication tag for [`Self::field_entropy_gcm`]
+    pub fn fe_tag(&self) -> [u8; GCM_TAG_SIZE] {
+        self.regs.borrow().fe_tag
+    }
+
+    /// Clear all secrets held in the SoC registers
+    pub fn clear_secrets(&self) {
+        let mut regs = self.regs.borrow_mut();
+        regs.doe_key = [0u8; DOE_KEY_SIZE];
+        regs.uds = [0u8; UDS_SIZE];
+        regs.uds_gcm = [0u8; UDS_SIZE];
+        regs.uds_tag = [0u8; GCM_TAG_SIZE];
+        regs.field_entropy = [0u8; FIELD_ENTROPY_SIZE];
+        regs.field_entropy_gcm = [0u8; FIELD_ENTROPY_USED_SIZE];
+        regs.fe_tag = [0u8; GCM_TAG_SIZE];
+    }
+}
+
+impl Default for SocRegisters {
+    fn default() -> Self {
+        Self::new()
+    }
+}